@@ -0,0 +1,189 @@
+/// A simple mutable implementation of the optimal string alignment distance
+/// (Damerau-Levenshtein restricted to one edit per substring) to keep memory
+/// allocations minimum.
+#[derive(Default)]
+pub struct DamerauLevenshtein {
+    rows: Vec<usize>,
+    chars: Vec<char>,
+}
+
+impl DamerauLevenshtein {
+    pub fn new() -> Self {
+        DamerauLevenshtein::with_size(128)
+    }
+
+    pub fn with_size(size: usize) -> Self {
+        DamerauLevenshtein {
+            rows: vec![0; size * 3],
+            chars: Vec::new(),
+        }
+    }
+
+    /// Computes the optimal string alignment distance between two strings,
+    /// counted in bytes.
+    pub fn distance(&mut self, a: &str, b: &str) -> usize {
+        self.ensure_capacity(b.len() + 1);
+        self.calculate(a.as_bytes(), b.as_bytes())
+    }
+
+    /// Computes the optimal string alignment distance between two strings,
+    /// counted in Unicode code points rather than bytes.
+    pub fn distance_chars(&mut self, a: &str, b: &str) -> usize {
+        let len_a = a.chars().count();
+        let len_b = b.chars().count();
+
+        self.ensure_capacity(len_b + 1);
+        self.ensure_char_capacity(len_a + len_b);
+
+        for (slot, ch) in self.chars.iter_mut().zip(a.chars().chain(b.chars())) {
+            *slot = ch;
+        }
+
+        // Safety: `self.chars` was just grown to hold at least `len_a + len_b`
+        // elements above, and populated with `a`'s chars followed by `b`'s
+        // chars, so both slices below stay in bounds and don't overlap.
+        let (a_chars, b_chars) = unsafe {
+            let ptr = self.chars.as_ptr();
+            (
+                std::slice::from_raw_parts(ptr, len_a),
+                std::slice::from_raw_parts(ptr.add(len_a), len_b),
+            )
+        };
+
+        self.calculate(a_chars, b_chars)
+    }
+
+    /// Computes a normalized similarity in `[0, 1]` from the byte-wise edit
+    /// distance, so it composes with [`crate::jarowinkler::JaroWinkler`]'s
+    /// scoring API.
+    pub fn similarity(&mut self, a: &str, b: &str) -> f64 {
+        Self::normalize(self.distance(a, b), a.len(), b.len())
+    }
+
+    /// Same as [`DamerauLevenshtein::similarity`], but counted in Unicode
+    /// code points rather than bytes.
+    pub fn similarity_chars(&mut self, a: &str, b: &str) -> f64 {
+        let len_a = a.chars().count();
+        let len_b = b.chars().count();
+        Self::normalize(self.distance_chars(a, b), len_a, len_b)
+    }
+
+    fn normalize(dist: usize, len_a: usize, len_b: usize) -> f64 {
+        let max_len = len_a.max(len_b);
+        if max_len == 0 {
+            1.0
+        } else {
+            1.0 - dist as f64 / max_len as f64
+        }
+    }
+
+    fn ensure_capacity(&mut self, row_len: usize) {
+        let current_capacity = self.rows.len() / 3;
+        if row_len <= current_capacity {
+            return;
+        }
+
+        let mut new_capacity = current_capacity * 2;
+        if new_capacity < row_len {
+            new_capacity = row_len;
+        }
+        self.rows = vec![0; new_capacity * 3];
+    }
+
+    fn ensure_char_capacity(&mut self, capacity: usize) {
+        let current_capacity = self.chars.len();
+        if capacity <= current_capacity {
+            return;
+        }
+
+        let mut new_capacity = current_capacity * 2;
+        if new_capacity < capacity {
+            new_capacity = capacity;
+        }
+        self.chars = vec!['\0'; new_capacity];
+    }
+
+    fn calculate<T: PartialEq>(&mut self, a: &[T], b: &[T]) -> usize {
+        let m = a.len();
+        let n = b.len();
+
+        if m == 0 {
+            return n;
+        }
+        if n == 0 {
+            return m;
+        }
+
+        let row_len = n + 1;
+        let (used, _) = self.rows.split_at_mut(3 * row_len);
+        let (prev2, rest) = used.split_at_mut(row_len);
+        let (mut prev1, mut curr) = rest.split_at_mut(row_len);
+        let mut prev2 = prev2;
+
+        for (j, slot) in prev1.iter_mut().enumerate() {
+            *slot = j;
+        }
+
+        for i in 1..=m {
+            curr[0] = i;
+
+            for j in 1..=n {
+                let cost = (a[i - 1] != b[j - 1]) as usize;
+                curr[j] = (prev1[j] + 1).min(curr[j - 1] + 1).min(prev1[j - 1] + cost);
+
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    curr[j] = curr[j].min(prev2[j - 2] + cost);
+                }
+            }
+
+            std::mem::swap(&mut prev2, &mut prev1);
+            std::mem::swap(&mut prev1, &mut curr);
+        }
+
+        prev1[n]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DamerauLevenshtein;
+
+    #[test]
+    fn identical_strings_have_zero_distance() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.distance("kitten", "kitten"), 0);
+    }
+
+    #[test]
+    fn classic_kitten_sitting() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn counts_a_transposition_as_one_edit() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.distance("ab", "ba"), 1);
+    }
+
+    #[test]
+    fn empty_strings_compare_against_the_other_length() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.distance("", "abc"), 3);
+        assert_eq!(dl.distance("abc", ""), 3);
+        assert_eq!(dl.distance("", ""), 0);
+    }
+
+    #[test]
+    fn distance_chars_counts_code_points_not_bytes() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.distance_chars("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn similarity_is_normalized_between_zero_and_one() {
+        let mut dl = DamerauLevenshtein::new();
+        assert_eq!(dl.similarity("kitten", "kitten"), 1.0);
+        assert_eq!(dl.similarity("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+    }
+}
@@ -1,22 +1,154 @@
+//! This module is `no_std`-compatible: the matching core (`Inner` and the
+//! free `calculate` function) only operates on slices, never an owned
+//! allocation. [`JaroWinkler::with_buffer`] and [`JaroWinkler::apply_in`] run
+//! on a caller-supplied scratch buffer and work without `alloc`, as do
+//! [`JaroWinkler::apply`] and the `with_*` builder methods on such a matcher;
+//! the `Vec`-backed constructors, `apply_chars` and `top_k` require the
+//! `alloc` feature.
+
+#[cfg(feature = "alloc")]
+use alloc::collections::BinaryHeap;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::cmp::Reverse;
+use core::mem;
+
+/// Default Winkler prefix scale `p`, applied to the boosted common prefix.
+const DEFAULT_PREFIX_SCALE: f64 = 0.1;
+/// Default number of leading characters considered for the prefix boost.
+const DEFAULT_MAX_PREFIX_LEN: usize = 4;
+/// Default boost threshold. `0.0` always applies the boost (the behavior this
+/// crate had before the threshold became configurable); the canonical Winkler
+/// definition commonly uses `0.7` instead, set via [`JaroWinkler::with_boost_threshold`].
+const DEFAULT_BOOST_THRESHOLD: f64 = 0.0;
+
+/// The `isize` scratch backing a [`JaroWinkler`]: either an owned, growable
+/// buffer (the `alloc`-gated convenience path) or a fixed-size buffer
+/// borrowed from the caller (the `no_std` path).
+enum Scratch<'a> {
+    #[cfg(feature = "alloc")]
+    Owned(Vec<isize>),
+    Borrowed(&'a mut [isize]),
+}
+
+impl<'a> Scratch<'a> {
+    fn as_mut_slice(&mut self) -> &mut [isize] {
+        match self {
+            #[cfg(feature = "alloc")]
+            Scratch::Owned(v) => v.as_mut_slice(),
+            Scratch::Borrowed(s) => s,
+        }
+    }
+
+    /// Grows an owned buffer to at least `capacity`. Borrowed buffers can't
+    /// grow, so this panics if `capacity` exceeds the buffer the caller gave
+    /// us; that's the tradeoff for running without an allocator.
+    fn ensure_capacity(&mut self, capacity: usize) {
+        match self {
+            #[cfg(feature = "alloc")]
+            Scratch::Owned(v) => {
+                let current_capacity = v.len();
+                if capacity <= current_capacity {
+                    return;
+                }
+                *v = vec![-1; grown_capacity(current_capacity, capacity)];
+            }
+            Scratch::Borrowed(s) => assert!(
+                capacity <= s.len(),
+                "JaroWinkler scratch buffer too small: need {} isize slots, have {}",
+                capacity,
+                s.len()
+            ),
+        }
+    }
+}
+
 /// A simple mutable implementation of Jaro-Winkler to
 /// keep memory allocations minimum.
-#[derive(Default)]
-pub struct JaroWinkler {
-    indices: Vec<isize>,
+pub struct JaroWinkler<'a> {
+    indices: Scratch<'a>,
+    #[cfg(feature = "alloc")]
+    chars: Vec<char>,
+    prefix_scale: f64,
+    max_prefix_len: usize,
+    boost_threshold: f64,
+}
+
+#[cfg(feature = "alloc")]
+impl Default for JaroWinkler<'static> {
+    fn default() -> Self {
+        JaroWinkler::new()
+    }
 }
 
-impl JaroWinkler {
+#[cfg(feature = "alloc")]
+impl JaroWinkler<'static> {
     pub fn new() -> Self {
         JaroWinkler::with_size(128)
     }
 
     pub fn with_size(size: usize) -> Self {
         JaroWinkler {
-            indices: vec![-1; size],
+            indices: Scratch::Owned(vec![-1; size]),
+            chars: Vec::new(),
+            prefix_scale: DEFAULT_PREFIX_SCALE,
+            max_prefix_len: DEFAULT_MAX_PREFIX_LEN,
+            boost_threshold: DEFAULT_BOOST_THRESHOLD,
         }
     }
+}
+
+impl<'a> JaroWinkler<'a> {
+    /// Builds a `no_std`-compatible matcher backed by a caller-supplied
+    /// scratch buffer instead of an owned allocation. `buffer` must hold at
+    /// least `s1.len() + s2.len()` `isize` slots for any pair passed to
+    /// [`JaroWinkler::apply`]; `apply` panics otherwise, since a borrowed
+    /// buffer can't grow the way the owned `Vec` does.
+    pub fn with_buffer(buffer: &'a mut [isize]) -> Self {
+        buffer.fill(-1);
+        JaroWinkler {
+            indices: Scratch::Borrowed(buffer),
+            #[cfg(feature = "alloc")]
+            chars: Vec::new(),
+            prefix_scale: DEFAULT_PREFIX_SCALE,
+            max_prefix_len: DEFAULT_MAX_PREFIX_LEN,
+            boost_threshold: DEFAULT_BOOST_THRESHOLD,
+        }
+    }
+
+    /// Sets the prefix weight `p` applied to the Winkler boost. Keep
+    /// `prefix_scale * max_prefix_len <= 1.0` to keep scores within `[0, 1]`.
+    pub fn with_prefix_scale(mut self, prefix_scale: f64) -> Self {
+        self.prefix_scale = prefix_scale;
+        self
+    }
+
+    /// Sets the number of leading characters considered for the prefix boost.
+    pub fn with_max_prefix_len(mut self, max_prefix_len: usize) -> Self {
+        self.max_prefix_len = max_prefix_len;
+        self
+    }
+
+    /// Sets the minimum base Jaro score required before the Winkler prefix
+    /// boost is applied. The canonical Winkler definition uses `0.7`; this
+    /// defaults to `0.0`, which always applies the boost.
+    pub fn with_boost_threshold(mut self, boost_threshold: f64) -> Self {
+        self.boost_threshold = boost_threshold;
+        self
+    }
 
     /// Match two input strings and produces a score between 0 and 1.
+    ///
+    /// Operates on raw bytes, so the matching window, transpositions and prefix
+    /// are all counted in bytes. This is the fastest path for ASCII-only input,
+    /// but will produce incorrect results for multi-byte UTF-8 sequences (use
+    /// [`JaroWinkler::apply_chars`] for those instead).
+    ///
+    /// Panics if this matcher was built with [`JaroWinkler::with_buffer`] and
+    /// the buffer is smaller than `s1.len() + s2.len()`.
     pub fn apply(&mut self, s1: &str, s2: &str) -> f64 {
         if s1.is_empty() && s2.is_empty() {
             return 1.0;
@@ -32,57 +164,261 @@ impl JaroWinkler {
         self.ensure_capacity(b1.len() + b2.len());
 
         if b1.len() > b2.len() {
-            std::mem::swap(&mut b1, &mut b2);
+            mem::swap(&mut b1, &mut b2);
         }
-        self.calculate(b1, b2)
+        calculate(
+            self.indices.as_mut_slice(),
+            b1,
+            b2,
+            self.prefix_scale,
+            self.max_prefix_len,
+            self.boost_threshold,
+        )
+    }
+
+    /// Match two input strings and produces a score between 0 and 1, counting
+    /// the matching window, transpositions and prefix in Unicode code points
+    /// rather than bytes.
+    ///
+    /// This is the correct mode for any input that may contain multi-byte
+    /// UTF-8 sequences (accented Latin, Cyrillic, CJK, ...), since byte-wise
+    /// matching can split a code point across the matching window or the
+    /// prefix and produce nonsensical scores.
+    #[cfg(feature = "alloc")]
+    pub fn apply_chars(&mut self, s1: &str, s2: &str) -> f64 {
+        if s1.is_empty() && s2.is_empty() {
+            return 1.0;
+        }
+
+        if s1.is_empty() || s2.is_empty() {
+            return 0.0;
+        }
+
+        let len1 = s1.chars().count();
+        let len2 = s2.chars().count();
+
+        self.ensure_capacity(len1 + len2);
+        self.ensure_char_capacity(len1 + len2);
+
+        let (min_str, min_len, max_str, max_len) = if len1 > len2 {
+            (s2, len2, s1, len1)
+        } else {
+            (s1, len1, s2, len2)
+        };
+
+        for (slot, ch) in self
+            .chars
+            .iter_mut()
+            .zip(min_str.chars().chain(max_str.chars()))
+        {
+            *slot = ch;
+        }
+
+        // Safety: `self.chars` was just grown to hold at least `min_len + max_len`
+        // elements above, and populated with `min_str`'s chars followed by
+        // `max_str`'s chars, so both slices below stay in bounds and don't overlap.
+        let (min, max) = unsafe {
+            let ptr = self.chars.as_ptr();
+            (
+                core::slice::from_raw_parts(ptr, min_len),
+                core::slice::from_raw_parts(ptr.add(min_len), max_len),
+            )
+        };
+
+        calculate(
+            self.indices.as_mut_slice(),
+            min,
+            max,
+            self.prefix_scale,
+            self.max_prefix_len,
+            self.boost_threshold,
+        )
+    }
+
+    /// Returns the `k` candidates from `candidates` with the highest Jaro-Winkler
+    /// score against `query`, sorted in descending order of score.
+    ///
+    /// Reuses the internal scratch buffer across every candidate, so scoring
+    /// allocates only when a query/candidate pair needs more scratch than the
+    /// buffer already holds, and keeps only `k` scored candidates in memory at
+    /// a time regardless of how many candidates are supplied.
+    #[cfg(feature = "alloc")]
+    pub fn top_k<'b, I>(&mut self, query: &str, candidates: I, k: usize) -> Vec<(f64, &'b str)>
+    where
+        I: IntoIterator<Item = &'b str>,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap: BinaryHeap<Reverse<ScoredCandidate<'b>>> = BinaryHeap::with_capacity(k);
+
+        for candidate in candidates {
+            let score = self.apply(query, candidate);
+
+            if heap.len() < k {
+                heap.push(Reverse(ScoredCandidate { score, candidate }));
+            } else if let Some(Reverse(lowest)) = heap.peek() {
+                if score > lowest.score {
+                    heap.pop();
+                    heap.push(Reverse(ScoredCandidate { score, candidate }));
+                }
+            }
+        }
+
+        let mut results: Vec<(f64, &'b str)> = heap
+            .into_iter()
+            .map(|Reverse(scored)| (scored.score, scored.candidate))
+            .collect();
+        results.sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        results
     }
 
     fn ensure_capacity(&mut self, capacity: usize) {
-        let current_capacity = self.indices.len();
+        self.indices.ensure_capacity(capacity);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ensure_char_capacity(&mut self, capacity: usize) {
+        let current_capacity = self.chars.len();
         if capacity <= current_capacity {
             return;
         }
+        self.chars = vec!['\0'; grown_capacity(current_capacity, capacity)];
+    }
+}
 
-        let mut new_capacity = current_capacity * 2;
-        if new_capacity < capacity {
-            new_capacity = capacity;
-        }
-        self.indices = vec![-1; new_capacity];
+/// Doubles `current`, clamping up to at least `requested` if doubling isn't
+/// enough, so repeated growth is amortized O(1) instead of re-allocating on
+/// every call whose input is only slightly larger than the last.
+#[cfg(feature = "alloc")]
+fn grown_capacity(current: usize, requested: usize) -> usize {
+    let doubled = current * 2;
+    if doubled < requested {
+        requested
+    } else {
+        doubled
     }
+}
 
-    fn calculate(&mut self, min: &[u8], max: &[u8]) -> f64 {
-        let mut inner = Inner::new(&mut self.indices, min, max);
-        let m = inner.matches();
+/// Matches two equal-comparable strings of code units (bytes or chars) over a
+/// caller-supplied scratch buffer, with no dependency on an owned allocation.
+/// This is the shared core behind both the `alloc`-backed [`JaroWinkler`] and
+/// its `no_std`, buffer-only entry points.
+fn calculate<T: PartialEq + Copy>(
+    scratch: &mut [isize],
+    min: &[T],
+    max: &[T],
+    prefix_scale: f64,
+    max_prefix_len: usize,
+    boost_threshold: f64,
+) -> f64 {
+    let mut inner = Inner::new(scratch, min, max);
+    let m = inner.matches();
 
-        if m == 0 {
-            return 0.0;
+    if m == 0 {
+        return 0.0;
+    }
+
+    let t = inner.transpositions(m) as f64;
+    let p = inner.prefix(max_prefix_len) as f64;
+    let min_len = min.len() as f64;
+    let max_len = max.len() as f64;
+    let m = m as f64;
+
+    let j = (m / min_len + m / max_len + (m - t) / m) / 3.0;
+    if j < boost_threshold {
+        j
+    } else {
+        j + prefix_scale * p * (1.0 - j)
+    }
+}
+
+impl JaroWinkler<'_> {
+    /// Matches two byte strings using a fixed-size, stack-allocated scratch
+    /// buffer and no heap at all: the `no_std`, allocation-free entry point.
+    ///
+    /// Returns `None` if `scratch` is smaller than `s1.len() + s2.len()`.
+    pub fn apply_in<const N: usize>(s1: &str, s2: &str, scratch: &mut [isize; N]) -> Option<f64> {
+        if s1.is_empty() && s2.is_empty() {
+            return Some(1.0);
+        }
+
+        if s1.is_empty() || s2.is_empty() {
+            return Some(0.0);
         }
 
-        let t = inner.transpositions(m) as f64;
-        let p = inner.prefix() as f64;
-        let min_len = min.len() as f64;
-        let max_len = max.len() as f64;
-        let m = m as f64;
+        let mut b1 = s1.as_bytes();
+        let mut b2 = s2.as_bytes();
+
+        if N < b1.len() + b2.len() {
+            return None;
+        }
+
+        if b1.len() > b2.len() {
+            mem::swap(&mut b1, &mut b2);
+        }
+
+        scratch[..b1.len() + b2.len()].fill(-1);
+        Some(calculate(
+            scratch.as_mut_slice(),
+            b1,
+            b2,
+            DEFAULT_PREFIX_SCALE,
+            DEFAULT_MAX_PREFIX_LEN,
+            DEFAULT_BOOST_THRESHOLD,
+        ))
+    }
+}
+
+/// A scored candidate for [`JaroWinkler::top_k`]. Jaro-Winkler scores are
+/// always finite and in `[0, 1]`, so this orders purely on the score and never
+/// has to contend with `NaN`.
+#[cfg(feature = "alloc")]
+struct ScoredCandidate<'a> {
+    score: f64,
+    candidate: &'a str,
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq for ScoredCandidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
 
-        let j = (m / min_len + m / max_len + (m - t) / m) / 3.0;
-        j + 0.1 * p * (1.0 - j)
+#[cfg(feature = "alloc")]
+impl Eq for ScoredCandidate<'_> {}
+
+#[cfg(feature = "alloc")]
+impl PartialOrd for ScoredCandidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-struct Inner<'a> {
+#[cfg(feature = "alloc")]
+impl Ord for ScoredCandidate<'_> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.score.partial_cmp(&other.score).unwrap()
+    }
+}
+
+struct Inner<'a, T> {
     min_indices: &'a mut [isize],
     max_flags: &'a mut [isize],
-    min: &'a [u8],
-    max: &'a [u8],
+    min: &'a [T],
+    max: &'a [T],
 }
 
-impl<'a> Inner<'a> {
-    fn new(indices: &'a mut [isize], min: &'a [u8], max: &'a [u8]) -> Self {
-        use std::slice::from_raw_parts_mut;
+impl<'a, T: PartialEq + Copy> Inner<'a, T> {
+    fn new(indices: &'a mut [isize], min: &'a [T], max: &'a [T]) -> Self {
+        use core::slice::from_raw_parts_mut;
         let ptr = indices.as_mut_ptr();
 
-        // Safety: we ensured that both `min` and `max` are non-zero length in `JaroWinkler::apply` method
-        // and `indices` is at least as large as `min.len() + max.len()` in `JaroWinkler::ensure_capacity`
+        // Safety: callers ensure both `min` and `max` are non-zero length, and that
+        // `indices` is at least as large as `min.len() + max.len()` (`JaroWinkler::apply`
+        // grows or checks the scratch buffer via `ensure_capacity` before calling in).
         unsafe {
             let min_indices = from_raw_parts_mut(ptr, min.len());
             let max_flags = from_raw_parts_mut(ptr.add(min.len()), max.len());
@@ -146,20 +482,63 @@ impl<'a> Inner<'a> {
         t / 2
     }
 
-    fn prefix(&self) -> usize {
+    fn prefix(&self, max_len: usize) -> usize {
         self.min
             .iter()
             .zip(self.max.iter())
-            .take(4)
+            .take(max_len)
             .take_while(|(a, b)| a == b)
             .count()
     }
 }
 
+// These tests only exercise the buffer-only, allocation-free API, so they
+// compile and run under `--no-default-features` (no `std`, no `alloc`) as
+// well as the default feature set — that's the whole point of this path.
 #[cfg(test)]
 mod tests {
     use super::JaroWinkler;
 
+    #[test]
+    fn with_buffer_matches_without_an_owned_allocation() {
+        let mut scratch = [-1isize; 32];
+        let mut jw = JaroWinkler::with_buffer(&mut scratch);
+        let score = jw.apply("Foo bar", "Food candybar");
+        assert_eq!(score, 0.7897435897435898);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn with_buffer_panics_when_scratch_is_too_small() {
+        let mut scratch = [-1isize; 2];
+        let mut jw = JaroWinkler::with_buffer(&mut scratch);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            jw.apply("Foo bar", "Food candybar")
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn apply_in_matches_on_a_stack_buffer() {
+        let mut scratch = [0isize; 32];
+        let score = JaroWinkler::apply_in("Foo bar", "Food candybar", &mut scratch);
+        assert_eq!(score, Some(0.7897435897435898));
+    }
+
+    #[test]
+    fn apply_in_returns_none_when_buffer_is_too_small() {
+        let mut scratch = [0isize; 2];
+        let score = JaroWinkler::apply_in("Foo bar", "Food candybar", &mut scratch);
+        assert_eq!(score, None);
+    }
+}
+
+// These tests exercise the `Vec`-backed convenience API (`new`, `apply_chars`,
+// `top_k`), so they need the `alloc` feature.
+#[cfg(all(test, feature = "alloc"))]
+mod alloc_tests {
+    use super::JaroWinkler;
+
     #[test]
     fn partial_match() {
         let mut jw = JaroWinkler::new();
@@ -180,4 +559,53 @@ mod tests {
         let score = jw.apply("Foobar", "pqxyz");
         assert_eq!(score, 0.0);
     }
+
+    #[test]
+    fn apply_chars_matches_accented_text() {
+        let mut jw = JaroWinkler::new();
+        let score = jw.apply_chars("café", "cafe");
+        assert_eq!(score, 0.8833333333333334);
+    }
+
+    #[test]
+    fn boost_threshold_skips_prefix_boost_below_threshold() {
+        let mut jw = JaroWinkler::new().with_boost_threshold(0.7);
+        let score = jw.apply("Foo bar", "Food candybar");
+        assert_eq!(score, 0.6996336996336997);
+    }
+
+    #[test]
+    fn custom_prefix_scale_and_max_len() {
+        let mut jw = JaroWinkler::new()
+            .with_prefix_scale(0.25)
+            .with_max_prefix_len(2);
+        let score = jw.apply("Foo bar", "Food candybar");
+        assert_eq!(score, 0.8498168498168499);
+    }
+
+    #[test]
+    fn top_k_returns_best_matches_sorted_descending() {
+        let mut jw = JaroWinkler::new();
+        let candidates = ["apple", "apple", "apricot", "banana", "grape"];
+        let results = jw.top_k("aple", candidates, 2);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].1, "apple");
+        assert_eq!(results[1].1, "apple");
+        assert!(results[0].0 >= results[1].0);
+    }
+
+    #[test]
+    fn top_k_zero_returns_empty() {
+        let mut jw = JaroWinkler::new();
+        let results = jw.top_k("aple", ["apple", "apple"], 0);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn apply_chars_full_match_on_cjk() {
+        let mut jw = JaroWinkler::new();
+        let score = jw.apply_chars("東京都", "東京都");
+        assert_eq!(score, 1.0);
+    }
 }